@@ -0,0 +1,45 @@
+// ============================================
+// Launch at Login
+// ============================================
+//
+// Unpod is a tray-resident notification app, so letting it start on boot
+// is a common ask. Backed by `tauri-plugin-autostart`, which handles the
+// login-item / registry / .desktop integration per platform.
+
+use tauri::AppHandle;
+use tauri_plugin_autostart::ManagerExt;
+use tauri_plugin_store::StoreExt;
+
+const STORE_KEY: &str = "autostartEnabled";
+
+/// Whether this launch was triggered by the autostart login item, which
+/// passes `--minimized` (see the `tauri_plugin_autostart::init` call in
+/// `main.rs`) so the tray-resident app doesn't steal focus on every boot.
+pub fn launched_minimized() -> bool {
+    std::env::args().any(|arg| arg == "--minimized")
+}
+
+fn persist(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let store = app.store("session.json").map_err(|e| e.to_string())?;
+    store.set(STORE_KEY, serde_json::json!(enabled));
+    store.save().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn autostart_is_enabled(app: AppHandle) -> Result<bool, String> {
+    app.autolaunch().is_enabled().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn autostart_enable(app: AppHandle) -> Result<bool, String> {
+    app.autolaunch().enable().map_err(|e| e.to_string())?;
+    persist(&app, true)?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn autostart_disable(app: AppHandle) -> Result<bool, String> {
+    app.autolaunch().disable().map_err(|e| e.to_string())?;
+    persist(&app, false)?;
+    Ok(true)
+}
@@ -0,0 +1,148 @@
+// ============================================
+// Server Data Directory
+// ============================================
+//
+// Lets users relocate where the backend stores its data. The chosen path
+// is persisted in `session.json` and handed to the server as `DATA_DIR`;
+// moving it requires the server to be stopped first since it may still
+// hold files there open.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::AppState;
+
+const STORE_KEY: &str = "serverDataDir";
+
+/// The currently configured data directory, falling back to the app's
+/// default data directory if nothing has been set yet.
+pub fn get_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let store = app.store("session.json").map_err(|e| e.to_string())?;
+
+    if let Some(path) = store.get(STORE_KEY).and_then(|v| v.as_str().map(PathBuf::from)) {
+        return Ok(path);
+    }
+
+    app.path().app_data_dir().map_err(|e| e.to_string())
+}
+
+fn persist_data_dir(app: &AppHandle, path: &Path) -> Result<(), String> {
+    let store = app.store("session.json").map_err(|e| e.to_string())?;
+    store.set(STORE_KEY, serde_json::json!(path.to_string_lossy().to_string()));
+    store.save().map_err(|e| e.to_string())
+}
+
+fn is_populated(dir: &Path) -> bool {
+    dir.read_dir()
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false)
+}
+
+fn count_files(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                count_files(&entry.path())
+            } else {
+                1
+            }
+        })
+        .sum()
+}
+
+/// Copy `src` to `dst`, emitting `data-dir://migration-progress` after each
+/// file so the frontend can show a progress bar during large migrations.
+fn copy_dir_recursive(
+    app: &AppHandle,
+    src: &Path,
+    dst: &Path,
+    total_files: usize,
+    copied_files: &mut usize,
+) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let dst_path = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_recursive(app, &entry.path(), &dst_path, total_files, copied_files)?;
+        } else {
+            fs::copy(entry.path(), &dst_path)?;
+            *copied_files += 1;
+            let _ = app.emit(
+                "data-dir://migration-progress",
+                serde_json::json!({ "copied": *copied_files, "total": total_files }),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Stop the server, copy its data directory from the currently configured
+/// location to `new_path`, persist the new path, then relaunch the server
+/// pointed at it. The old directory's contents are left in place. Rolls
+/// back (keeps the old path, discards a partial copy) if the copy fails.
+#[tauri::command]
+pub async fn set_server_data_dir(app: AppHandle, new_path: String) -> Result<String, String> {
+    let new_dir = PathBuf::from(&new_path);
+    let old_dir = get_data_dir(&app)?;
+
+    if new_dir == old_dir {
+        return Ok(new_path);
+    }
+
+    if new_dir.exists() && is_populated(&new_dir) {
+        return Err(format!(
+            "Target directory '{}' already has files in it",
+            new_path
+        ));
+    }
+
+    crate::server::stop_and_wait(&app).await?;
+
+    if old_dir.exists() {
+        let total_files = count_files(&old_dir);
+        let mut copied_files = 0;
+        if let Err(e) = copy_dir_recursive(&app, &old_dir, &new_dir, total_files, &mut copied_files) {
+            let _ = fs::remove_dir_all(&new_dir);
+
+            // The server is still stopped from above - bring it back up
+            // against the *old* data dir before surfacing the error, and
+            // make sure `state.server_process` tracks whatever PID that
+            // gives us rather than the now-dead old one.
+            match crate::server::start_server_supervised(&app) {
+                Ok(pid) => {
+                    if let Some(state) = app.try_state::<AppState>() {
+                        *state.server_process.lock().unwrap() = Some(pid);
+                    }
+                }
+                Err(restart_err) => {
+                    eprintln!("Failed to restart server after aborted migration: {}", restart_err);
+                }
+            }
+
+            return Err(format!("Failed to migrate data to '{}': {}", new_path, e));
+        }
+    }
+
+    persist_data_dir(&app, &new_dir)?;
+
+    match crate::server::start_server_supervised(&app) {
+        Ok(pid) => {
+            if let Some(state) = app.try_state::<AppState>() {
+                *state.server_process.lock().unwrap() = Some(pid);
+            }
+            Ok(new_path)
+        }
+        Err(e) => Err(format!(
+            "Data directory moved to '{}', but failed to relaunch the server: {}",
+            new_path, e
+        )),
+    }
+}
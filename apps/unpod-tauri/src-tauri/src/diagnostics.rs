@@ -0,0 +1,109 @@
+// ============================================
+// Diagnostics & Repair
+// ============================================
+//
+// A window and a handful of commands for troubleshooting a misbehaving
+// server: what version is running, its recent stdout/stderr, and a
+// one-click "repair" that clears its cache/lock files and relaunches it.
+
+use std::io::{BufRead, BufReader, Read};
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::AppState;
+
+const MAX_LOG_LINES: usize = 2000;
+
+/// Append lines read from a server stdout/stderr pipe to the shared log
+/// ring buffer, tagged with which stream they came from.
+pub fn spawn_log_reader<R: Read + Send + 'static>(app: AppHandle, reader: R, stream_name: &'static str) {
+    std::thread::spawn(move || {
+        let buffered = BufReader::new(reader);
+        for line in buffered.lines() {
+            let Ok(line) = line else { break };
+
+            if let Some(state) = app.try_state::<AppState>() {
+                let mut log = state.server_log.lock().unwrap();
+                log.push_back(format!("[{}] {}", stream_name, line));
+                while log.len() > MAX_LOG_LINES {
+                    log.pop_front();
+                }
+            }
+        }
+    });
+}
+
+/// `CARGO_PKG_VERSION`, tagged `(debug mode)` under debug builds.
+fn version_tag() -> String {
+    if cfg!(debug_assertions) {
+        format!("{} (debug mode)", env!("CARGO_PKG_VERSION"))
+    } else {
+        env!("CARGO_PKG_VERSION").to_string()
+    }
+}
+
+#[tauri::command]
+pub fn get_backend_version() -> String {
+    version_tag()
+}
+
+#[tauri::command]
+pub fn tail_server_log(app: AppHandle, lines: usize) -> Result<Vec<String>, String> {
+    let state = app.try_state::<AppState>().ok_or("App state unavailable")?;
+    let log = state.server_log.lock().unwrap();
+    Ok(log.iter().rev().take(lines).rev().cloned().collect())
+}
+
+#[tauri::command]
+pub fn get_server_health(app: AppHandle) -> Result<serde_json::Value, String> {
+    let state = app.try_state::<AppState>().ok_or("App state unavailable")?;
+
+    Ok(serde_json::json!({
+        "pid": *state.server_process.lock().unwrap(),
+        "restart_count": *state.restart_count.lock().unwrap(),
+        "healthy": *state.healthy.lock().unwrap(),
+    }))
+}
+
+/// Stop the server, clear its cache/lock files, and relaunch it.
+#[tauri::command]
+pub async fn repair_server(app: AppHandle) -> Result<(), String> {
+    crate::server::stop_and_wait(&app).await?;
+
+    if let Ok(data_dir) = crate::data_dir::get_data_dir(&app) {
+        let _ = std::fs::remove_dir_all(data_dir.join("cache"));
+        let _ = std::fs::remove_file(data_dir.join("server.lock"));
+    }
+
+    let pid = crate::server::start_server_supervised(&app)?;
+    if let Some(state) = app.try_state::<AppState>() {
+        *state.server_process.lock().unwrap() = Some(pid);
+    }
+
+    Ok(())
+}
+
+/// Open (or focus) the diagnostics window.
+#[tauri::command]
+pub fn open_diagnostics_window(app: AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window("diagnostics") {
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    WebviewWindowBuilder::new(
+        &app,
+        "diagnostics",
+        WebviewUrl::External(
+            format!("{}/diagnostics", crate::server::server_origin())
+                .parse()
+                .map_err(|e| format!("{}", e))?,
+        ),
+    )
+    .title("Unpod Diagnostics")
+    .inner_size(640.0, 480.0)
+    .build()
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
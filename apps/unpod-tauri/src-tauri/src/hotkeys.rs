@@ -0,0 +1,161 @@
+// ============================================
+// Global Hotkeys
+// ============================================
+//
+// Lets the user summon the window (or hard-reload the server page) from
+// anywhere, even when Unpod is hidden behind other apps. Accelerators are
+// persisted in `session.json` so custom bindings survive a restart.
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+use tauri_plugin_store::StoreExt;
+
+pub const DEFAULT_TOGGLE_SHORTCUT: &str = "CmdOrCtrl+Shift+U";
+pub const DEFAULT_RELOAD_SHORTCUT: &str = "CmdOrCtrl+Shift+R";
+
+fn store_key_for(which: &str) -> Result<&'static str, String> {
+    match which {
+        "toggle" => Ok("toggleShortcut"),
+        "reload" => Ok("reloadShortcut"),
+        other => Err(format!("Unknown shortcut slot: {}", other)),
+    }
+}
+
+fn default_for(which: &str) -> Result<&'static str, String> {
+    match which {
+        "toggle" => Ok(DEFAULT_TOGGLE_SHORTCUT),
+        "reload" => Ok(DEFAULT_RELOAD_SHORTCUT),
+        other => Err(format!("Unknown shortcut slot: {}", other)),
+    }
+}
+
+// Keyed by `Shortcut::to_string()` (the parsed accelerator's canonical
+// form), not the raw string the user/store handed us, since that's what
+// `handle_shortcut_event` looks the action up by.
+fn set_action(app: &AppHandle, shortcut: &Shortcut, which: &str) {
+    if let Some(state) = app.try_state::<crate::AppState>() {
+        state
+            .hotkey_actions
+            .lock()
+            .unwrap()
+            .insert(shortcut.to_string(), which.to_string());
+    }
+}
+
+fn remove_action(app: &AppHandle, shortcut: &Shortcut) {
+    if let Some(state) = app.try_state::<crate::AppState>() {
+        state.hotkey_actions.lock().unwrap().remove(&shortcut.to_string());
+    }
+}
+
+#[tauri::command]
+pub fn shortcut_get(app: AppHandle, which: String) -> Result<String, String> {
+    let key = store_key_for(&which)?;
+    let default = default_for(&which)?;
+
+    let store = app.store("session.json").map_err(|e| e.to_string())?;
+    Ok(store
+        .get(key)
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| default.to_string()))
+}
+
+#[tauri::command]
+pub fn shortcut_set(app: AppHandle, which: String, accelerator: String) -> Result<bool, String> {
+    let key = store_key_for(&which)?;
+    let shortcut: Shortcut = accelerator
+        .parse()
+        .map_err(|e| format!("Invalid accelerator '{}': {}", accelerator, e))?;
+
+    // Release whatever is currently bound to this slot first.
+    let previous = shortcut_get(app.clone(), which.clone())?;
+    if let Ok(prev_shortcut) = previous.parse::<Shortcut>() {
+        let _ = app.global_shortcut().unregister(prev_shortcut);
+        remove_action(&app, &prev_shortcut);
+    }
+
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|e| format!("Failed to register '{}' (already in use?): {}", accelerator, e))?;
+    set_action(&app, &shortcut, &which);
+
+    let store = app.store("session.json").map_err(|e| e.to_string())?;
+    store.set(key, serde_json::json!(accelerator));
+    store.save().map_err(|e| e.to_string())?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn shortcut_unregister(app: AppHandle, which: String) -> Result<bool, String> {
+    let key = store_key_for(&which)?;
+    let current = shortcut_get(app.clone(), which.clone())?;
+
+    if let Ok(shortcut) = current.parse::<Shortcut>() {
+        app.global_shortcut()
+            .unregister(shortcut)
+            .map_err(|e| e.to_string())?;
+        remove_action(&app, &shortcut);
+    }
+
+    let store = app.store("session.json").map_err(|e| e.to_string())?;
+    store.delete(key);
+    store.save().map_err(|e| e.to_string())?;
+
+    Ok(true)
+}
+
+/// Register the toggle and reload shortcuts with whatever accelerators are
+/// persisted (or the defaults, on first run). Call after `create_tray` so
+/// the tray and menu already exist.
+pub fn register_defaults(app: &AppHandle) {
+    for which in ["toggle", "reload"] {
+        match shortcut_get(app.clone(), which.to_string()) {
+            Ok(accelerator) => {
+                if let Err(e) = shortcut_set(app.clone(), which.to_string(), accelerator) {
+                    eprintln!("Failed to register default '{}' shortcut: {}", which, e);
+                }
+            }
+            Err(e) => eprintln!("Failed to read '{}' shortcut: {}", which, e),
+        }
+    }
+}
+
+/// Handler passed to `tauri_plugin_global_shortcut::Builder::with_handler`.
+/// Dispatches based on which action the fired accelerator is currently
+/// bound to in `AppState.hotkey_actions`.
+pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+
+    let action = app
+        .try_state::<crate::AppState>()
+        .and_then(|state| state.hotkey_actions.lock().unwrap().get(&shortcut.to_string()).cloned());
+
+    match action.as_deref() {
+        Some("toggle") => handle_toggle(app),
+        Some("reload") => handle_reload(app),
+        _ => {}
+    }
+}
+
+fn handle_toggle(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    if window.is_focused().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        crate::activate_app_window(app);
+    }
+}
+
+fn handle_reload(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        if let Ok(url) = crate::server::server_origin().parse() {
+            let _ = window.navigate(url);
+        }
+    }
+}
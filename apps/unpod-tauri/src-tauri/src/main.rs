@@ -4,22 +4,40 @@
 use tauri::{
     Manager, AppHandle, State, Window, Listener,
     image::Image,
-    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
 };
 use tauri_plugin_store::StoreExt;
 use tauri_plugin_opener::OpenerExt;
 use tauri_plugin_notification::NotificationExt;
 use std::sync::Mutex;
+use std::time::Instant;
 
 // Only import DialogExt in production builds where it's used
 #[cfg(not(debug_assertions))]
 use tauri_plugin_dialog::DialogExt;
 
+mod autostart;
+mod data_dir;
+mod diagnostics;
+mod hotkeys;
+mod proxy;
+mod server;
+mod single_instance;
+mod theme;
+mod updater;
+mod uri_scheme;
+
 // State for managing notification count and server process
 struct AppState {
     notification_count: Mutex<u32>,
     server_process: Mutex<Option<u32>>, // Store PID of the Node.js server process
+    restart_count: Mutex<u32>,          // How many times the watchdog has restarted the server
+    last_ready: Mutex<Option<Instant>>, // When the server was last confirmed healthy
+    healthy: Mutex<bool>, // Whether the watchdog currently considers the server healthy
+    hotkey_actions: Mutex<std::collections::HashMap<String, String>>, // accelerator -> action name
+    shutting_down: Mutex<bool>, // set once teardown starts, so the watchdog stops restarting
+    server_log: Mutex<std::collections::VecDeque<String>>, // ring buffer of recent server stdout/stderr lines
 }
 
 // ============================================
@@ -109,14 +127,21 @@ fn get_platform() -> String {
 
 #[tauri::command]
 fn get_app_version(app: AppHandle) -> String {
-    app.package_info().version.to_string()
+    let version = app.package_info().version.to_string();
+    if cfg!(debug_assertions) {
+        format!("{} (debug mode)", version)
+    } else {
+        version
+    }
 }
 
 #[tauri::command]
-fn get_theme() -> String {
-    // Note: Theme detection would require platform-specific implementation
-    // For now, return a default value
-    "light".to_string()
+fn get_theme(app: AppHandle) -> String {
+    app.get_webview_window("main")
+        .and_then(|window| window.theme().ok())
+        .map(theme::theme_str)
+        .unwrap_or("light")
+        .to_string()
 }
 
 // ============================================
@@ -330,58 +355,6 @@ fn update_notification_badge(
     Ok(())
 }
 
-// ============================================
-// Auto-Updater Commands
-// ============================================
-
-#[tauri::command]
-async fn updater_check_for_updates(_app: AppHandle) -> Result<String, String> {
-    #[cfg(not(debug_assertions))]
-    {
-        use tauri_plugin_updater::UpdaterExt;
-
-        let updater = _app.updater_builder().build()
-            .map_err(|e| e.to_string())?;
-
-        match updater.check().await {
-            Ok(Some(update)) => {
-                Ok(format!("Update available: {}", update.version))
-            }
-            Ok(None) => Ok("No update available".to_string()),
-            Err(e) => Err(e.to_string()),
-        }
-    }
-
-    #[cfg(debug_assertions)]
-    {
-        Err("Auto-updates disabled in development".to_string())
-    }
-}
-
-#[tauri::command]
-async fn updater_download_and_install(_app: AppHandle) -> Result<(), String> {
-    #[cfg(not(debug_assertions))]
-    {
-        use tauri_plugin_updater::UpdaterExt;
-
-        let updater = _app.updater_builder().build()
-            .map_err(|e| e.to_string())?;
-
-        if let Some(update) = updater.check().await.map_err(|e| e.to_string())? {
-            update.download_and_install(|_chunk_length, _content_length| {}, || {})
-                .await
-                .map_err(|e| e.to_string())?;
-        }
-
-        Ok(())
-    }
-
-    #[cfg(debug_assertions)]
-    {
-        Err("Auto-updates disabled in development".to_string())
-    }
-}
-
 // ============================================
 // System Tray Setup
 // ============================================
@@ -390,6 +363,16 @@ fn create_tray(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>>
     // Create tray menu
     let show_item = MenuItem::with_id(app, "show", "Show App", true, None::<&str>)?;
     let check_updates_item = MenuItem::with_id(app, "check_updates", "Check for Updates", true, None::<&str>)?;
+    let autostart_enabled = autostart::autostart_is_enabled(app.clone()).unwrap_or(false);
+    let autostart_item = CheckMenuItem::with_id(
+        app,
+        "autostart",
+        "Start Unpod at Login",
+        true,
+        autostart_enabled,
+        None::<&str>,
+    )?;
+    let diagnostics_item = MenuItem::with_id(app, "diagnostics", "Diagnostics...", true, None::<&str>)?;
     let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
 
     let menu = Menu::with_items(
@@ -398,6 +381,8 @@ fn create_tray(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>>
             &show_item,
             &PredefinedMenuItem::separator(app)?,
             &check_updates_item,
+            &autostart_item,
+            &diagnostics_item,
             &PredefinedMenuItem::separator(app)?,
             &quit_item,
         ],
@@ -408,11 +393,12 @@ fn create_tray(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>>
     let icon = Image::from_bytes(icon_bytes)?;
 
     // Create tray icon
-    let _tray = TrayIconBuilder::new()
+    let autostart_item_for_events = autostart_item.clone();
+    let _tray = TrayIconBuilder::with_id("main-tray")
         .icon(icon)
         .menu(&menu)
         .tooltip("Unpod")
-        .on_menu_event(|app, event| {
+        .on_menu_event(move |app, event| {
             match event.id().as_ref() {
                 "show" => {
                     if let Some(window) = app.get_webview_window("main") {
@@ -423,12 +409,30 @@ fn create_tray(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>>
                 "check_updates" => {
                     let app = app.clone();
                     tauri::async_runtime::spawn(async move {
-                        match updater_check_for_updates(app).await {
-                            Ok(msg) => println!("{}", msg),
-                            Err(e) => eprintln!("Update check failed: {}", e),
+                        if let Err(e) = updater::updater_install_with_dialog(app).await {
+                            eprintln!("Update check failed: {}", e);
                         }
                     });
                 }
+                "autostart" => {
+                    let enabled = autostart::autostart_is_enabled(app.clone()).unwrap_or(false);
+                    let result = if enabled {
+                        autostart::autostart_disable(app.clone())
+                    } else {
+                        autostart::autostart_enable(app.clone())
+                    };
+                    match result {
+                        Ok(_) => {
+                            let _ = autostart_item_for_events.set_checked(!enabled);
+                        }
+                        Err(e) => eprintln!("Failed to toggle autostart: {}", e),
+                    }
+                }
+                "diagnostics" => {
+                    if let Err(e) = diagnostics::open_diagnostics_window(app.clone()) {
+                        eprintln!("Failed to open diagnostics window: {}", e);
+                    }
+                }
                 "quit" => {
                     app.exit(0);
                 }
@@ -521,123 +525,17 @@ fn create_menu(app: &tauri::AppHandle) -> Result<tauri::menu::Menu<tauri::Wry>,
     Ok(menu)
 }
 
-// ============================================
-// Server Management
-// ============================================
-
-/// Start the Next.js server process
-fn start_server(
-    #[allow(unused_variables)] app: &AppHandle
-) -> Result<u32, Box<dyn std::error::Error>> {
-    println!("Starting Next.js server...");
-
-    // In development, server is already running - skip
-    #[cfg(debug_assertions)]
-    {
-        println!("Development mode - assuming server is already running on localhost:3000");
-        return Ok(0); // Return dummy PID
-    }
-
-    #[cfg(not(debug_assertions))]
-    {
-        use std::process::{Command, Stdio};
-
-        // Get the resource directory where we'll bundle the Next.js server
-        let resource_dir = app.path().resource_dir()?;
-        let server_dir = resource_dir.join("server");
-
-        println!("Server directory: {:?}", server_dir);
-
-        // Check if server directory exists
-        if !server_dir.exists() {
-            return Err(format!("Server directory not found: {:?}", server_dir).into());
-        }
-
-        // Get the bundled Node.js binary path
-        // Platform-specific paths for external binaries
-        let node_path = if cfg!(target_os = "macos") {
-            // On macOS, external binaries go to Contents/MacOS/
-            resource_dir.parent()
-                .ok_or("Failed to get parent directory")?
-                .join("MacOS")
-                .join("node")
-        } else if cfg!(target_os = "windows") {
-            // On Windows, external binaries go to the same directory as the .exe
-            // They are bundled with .exe extension
-            let exe_dir = std::env::current_exe()?
-                .parent()
-                .ok_or("Failed to get exe directory")?
-                .to_path_buf();
-            exe_dir.join("node.exe")
-        } else {
-            // On Linux, external binaries are in the resources directory
-            resource_dir.join("node")
-        };
-
-        println!("Using bundled Node.js at: {:?}", node_path);
-
-        // Verify Node.js binary exists
-        if !node_path.exists() {
-            return Err(format!("Bundled Node.js not found at: {:?}\n\nPlease ensure Node.js is installed.", node_path).into());
-        }
-
-        // Start the Next.js standalone server
-        let server_script = server_dir.join("server.js");
-
-        println!("Starting server with bundled Node.js: {:?} {:?}", node_path, server_script);
-
-        let child = Command::new(&node_path)
-            .arg(server_script.to_str().unwrap())
-            .current_dir(&server_dir)
-            .env("NODE_ENV", "production")
-            .env("PORT", "3000")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn()?;
-
-        let pid = child.id();
-        println!("Server started with PID: {}", pid);
-
-        // Give the server a moment to start
-        std::thread::sleep(std::time::Duration::from_secs(2));
-
-        Ok(pid)
-    }
-}
-
-/// Stop the server process
-fn stop_server(pid: u32) {
-    if pid == 0 {
-        return; // Development mode or invalid PID
-    }
-
-    println!("Stopping server process (PID: {})...", pid);
-
-    #[cfg(target_os = "windows")]
-    {
-        use std::process::Command;
-        let _ = Command::new("taskkill")
-            .args(&["/PID", &pid.to_string(), "/F"])
-            .output();
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        use std::process::Command;
-        let _ = Command::new("kill")
-            .arg(pid.to_string())
-            .output();
-    }
-
-    println!("Server stopped");
-}
-
 // ============================================
 // Main Application
 // ============================================
 
 fn main() {
     tauri::Builder::default()
+        // Must be registered first so it can intercept a second launch
+        // before any other plugin (or the server) gets a chance to start.
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            single_instance::handle_second_instance(app, args, cwd);
+        }))
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::default().build())
@@ -645,9 +543,25 @@ fn main() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_notification::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(hotkeys::handle_shortcut_event)
+                .build(),
+        )
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec!["--minimized"]),
+        ))
+        .register_asynchronous_uri_scheme_protocol("unpod", uri_scheme::handle_request)
         .manage(AppState {
             notification_count: Mutex::new(0),
             server_process: Mutex::new(None),
+            restart_count: Mutex::new(0),
+            last_ready: Mutex::new(None),
+            healthy: Mutex::new(false),
+            hotkey_actions: Mutex::new(std::collections::HashMap::new()),
+            shutting_down: Mutex::new(false),
+            server_log: Mutex::new(std::collections::VecDeque::new()),
         })
         .invoke_handler(tauri::generate_handler![
             // Session management
@@ -673,19 +587,54 @@ fn main() {
             show_notification,
             update_notification_badge,
             // Updater
-            updater_check_for_updates,
-            updater_download_and_install,
+            updater::updater_check_for_updates,
+            updater::updater_download_and_install,
+            updater::updater_install_with_dialog,
+            // Proxy
+            proxy::proxy_get,
+            proxy::proxy_set,
+            proxy::proxy_clear,
+            // Global hotkeys
+            hotkeys::shortcut_get,
+            hotkeys::shortcut_set,
+            hotkeys::shortcut_unregister,
+            // Launch at login
+            autostart::autostart_is_enabled,
+            autostart::autostart_enable,
+            autostart::autostart_disable,
+            // Server management
+            server::restart_server,
+            server::server_ready_timeout_get,
+            server::server_ready_timeout_set,
+            data_dir::set_server_data_dir,
+            // Diagnostics
+            diagnostics::get_backend_version,
+            diagnostics::tail_server_log,
+            diagnostics::get_server_health,
+            diagnostics::repair_server,
+            diagnostics::open_diagnostics_window,
         ])
         .setup(|app| {
-            // Start the Next.js server first
-            match start_server(&app.handle()) {
+            // Launched via the autostart login item with `--minimized` -
+            // keep the window tucked away in the tray instead of showing it.
+            if autostart::launched_minimized() {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.hide();
+                }
+            }
+
+            // Start the Next.js server and wait for it to actually answer
+            // requests before continuing (replaces the old blind sleep(2s)).
+            match server::start_server_supervised(&app.handle()) {
                 Ok(pid) => {
                     println!("Server started successfully with PID: {}", pid);
-                    // Store the PID in app state
                     if let Some(state) = app.try_state::<AppState>() {
-                        let mut server_process = state.server_process.lock().unwrap();
-                        *server_process = Some(pid);
+                        *state.server_process.lock().unwrap() = Some(pid);
+                        *state.last_ready.lock().unwrap() = Some(Instant::now());
+                        *state.healthy.lock().unwrap() = true;
                     }
+                    // Keep it alive: probe periodically and restart on failure.
+                    server::spawn_watchdog(app.handle().clone());
                 }
                 Err(e) => {
                     eprintln!("Failed to start server: {}", e);
@@ -707,6 +656,20 @@ fn main() {
             // Create system tray
             create_tray(&app.handle()).expect("Failed to create tray");
 
+            // Pick a dark-mode-appropriate tray icon from the start.
+            if let Some(window) = app.get_webview_window("main") {
+                if let Ok(current_theme) = window.theme() {
+                    theme::sync_tray_icon(&app.handle(), current_theme);
+                }
+            }
+
+            // Register the toggle / hard-reload global hotkeys.
+            hotkeys::register_defaults(&app.handle());
+
+            // If a proxy was configured in a previous session, rebuild the
+            // main webview so it picks it up before the page loads.
+            proxy::apply_to_main_webview(&app.handle());
+
             // Setup notification click handler using event listeners
             // Try multiple event names to catch notification clicks
             let app_handle_1 = app.handle().clone();
@@ -736,17 +699,21 @@ fn main() {
                 activate_app_window(&app_handle_activation);
             });
 
-            // Also handle window events - when window gains focus, ensure it's fully visible
+            // Also handle window events - when window gains focus, ensure it's
+            // fully visible, and react to the OS appearance flipping.
             if let Some(window) = app.get_webview_window("main") {
-                let app_for_focus = app.handle().clone();
+                let app_for_events = app.handle().clone();
                 window.on_window_event(move |event| {
                     match event {
                         tauri::WindowEvent::Focused(focused) => {
                             if *focused {
                                 println!("Window focused - ensuring visibility");
-                                activate_app_window(&app_for_focus);
+                                activate_app_window(&app_for_events);
                             }
                         }
+                        tauri::WindowEvent::ThemeChanged(theme) => {
+                            theme::handle_theme_changed(&app_for_events, *theme);
+                        }
                         _ => {}
                     }
                 });
@@ -760,20 +727,25 @@ fn main() {
                 }
             }
 
-            // Setup cleanup handler to stop server when app exits
-            let app_for_cleanup = app.handle().clone();
-            app.listen("tauri://close-requested", move |_| {
-                println!("App closing - stopping server...");
-                if let Some(state) = app_for_cleanup.try_state::<AppState>() {
+            Ok(())
+        })
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // On every exit request (tray "Quit", Cmd+Q, or the last window
+            // being closed) stop the server and tell the watchdog to stand
+            // down. Closing the main window is not intercepted: when it is
+            // the last window, the app exits and the server stops with it.
+            if let tauri::RunEvent::ExitRequested { .. } = event {
+                println!("App exiting - stopping server...");
+                if let Some(state) = app_handle.try_state::<AppState>() {
+                    *state.shutting_down.lock().unwrap() = true;
+
                     let server_process = state.server_process.lock().unwrap();
                     if let Some(pid) = *server_process {
-                        stop_server(pid);
+                        server::stop_server(pid);
                     }
                 }
-            });
-
-            Ok(())
-        })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+            }
+        });
 }
@@ -0,0 +1,140 @@
+// ============================================
+// Outbound Proxy Configuration
+// ============================================
+//
+// Lets users behind a corporate or privacy proxy route the embedded
+// server and webview traffic through it. The URL is persisted in
+// `session.json` alongside the other session_* values.
+
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_store::StoreExt;
+
+const STORE_KEY: &str = "proxyUrl";
+
+fn validate_proxy_url(url: &str) -> Result<(), String> {
+    if url.starts_with("http://") || url.starts_with("https://") || url.starts_with("socks5://") {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unsupported proxy scheme in '{}': expected http://, https://, or socks5://",
+            url
+        ))
+    }
+}
+
+#[tauri::command]
+pub fn proxy_get(app: AppHandle) -> Result<Option<String>, String> {
+    let store = app.store("session.json").map_err(|e| e.to_string())?;
+
+    match store.get(STORE_KEY) {
+        Some(value) => Ok(value.as_str().map(|s| s.to_string())),
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+pub fn proxy_set(app: AppHandle, url: String) -> Result<bool, String> {
+    validate_proxy_url(&url)?;
+
+    let store = app.store("session.json").map_err(|e| e.to_string())?;
+    store.set(STORE_KEY, serde_json::json!(url));
+    store.save().map_err(|e| e.to_string())?;
+
+    let _ = app.emit("proxy://changed", Some(url));
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn proxy_clear(app: AppHandle) -> Result<bool, String> {
+    let store = app.store("session.json").map_err(|e| e.to_string())?;
+    store.delete(STORE_KEY);
+    store.save().map_err(|e| e.to_string())?;
+
+    let _ = app.emit("proxy://changed", Option::<String>::None);
+    Ok(true)
+}
+
+/// Read the stored proxy URL, if any, without going through the IPC layer.
+fn get_stored_proxy(app: &AppHandle) -> Option<String> {
+    app.store("session.json")
+        .ok()?
+        .get(STORE_KEY)?
+        .as_str()
+        .map(|s| s.to_string())
+}
+
+/// `HTTP_PROXY` / `HTTPS_PROXY` / `NO_PROXY` env vars to pass to the spawned
+/// Node server, based on the stored proxy configuration. Empty if unset.
+pub fn server_env_vars(app: &AppHandle) -> Vec<(String, String)> {
+    match get_stored_proxy(app) {
+        Some(url) => vec![
+            ("HTTP_PROXY".to_string(), url.clone()),
+            ("HTTPS_PROXY".to_string(), url),
+            ("NO_PROXY".to_string(), "127.0.0.1,localhost".to_string()),
+        ],
+        None => Vec::new(),
+    }
+}
+
+/// Apply the stored proxy (if any) to the main webview by tearing it down
+/// and rebuilding it with `proxy_url` set. No-op if no proxy is configured
+/// or the main window hasn't been created yet.
+pub fn apply_to_main_webview(app: &AppHandle) {
+    let Some(proxy_url) = get_stored_proxy(app) else {
+        return;
+    };
+
+    let parsed_proxy_url = match proxy_url.parse() {
+        Ok(url) => url,
+        Err(e) => {
+            eprintln!("Stored proxy URL is invalid, skipping: {}", e);
+            return;
+        }
+    };
+
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    // Rebuilding the webview loses every bit of window state that isn't
+    // passed back into the new builder, so capture whatever we can before
+    // tearing it down.
+    let current_url = window
+        .url()
+        .unwrap_or_else(|_| "index.html".parse().unwrap());
+    let title = window.title().unwrap_or_else(|_| "Unpod".to_string());
+    let decorated = window.is_decorated().unwrap_or(true);
+    let resizable = window.is_resizable().unwrap_or(true);
+    let maximized = window.is_maximized().unwrap_or(false);
+    let visible = window.is_visible().unwrap_or(true);
+    let size = window.inner_size().ok();
+    let position = window.outer_position().ok();
+
+    if let Err(e) = window.close() {
+        eprintln!("Failed to close main window before applying proxy: {}", e);
+        return;
+    }
+
+    let mut builder = WebviewWindowBuilder::new(app, "main", WebviewUrl::External(current_url))
+        .title(title)
+        .decorations(decorated)
+        .resizable(resizable)
+        .visible(visible)
+        .proxy_url(parsed_proxy_url);
+
+    if let Some(size) = size {
+        builder = builder.inner_size(size.width as f64, size.height as f64);
+    }
+    if let Some(position) = position {
+        builder = builder.position(position.x as f64, position.y as f64);
+    }
+
+    match builder.build() {
+        Ok(rebuilt) => {
+            if maximized {
+                let _ = rebuilt.maximize();
+            }
+        }
+        Err(e) => eprintln!("Failed to rebuild main webview with proxy: {}", e),
+    }
+}
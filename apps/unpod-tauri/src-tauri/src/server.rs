@@ -0,0 +1,435 @@
+// ============================================
+// Server Supervisor
+// ============================================
+//
+// Owns the lifecycle of the bundled Next.js server: spawns the Node
+// process, waits for it to actually answer requests before the app
+// finishes setup, and runs a background watchdog that restarts it with
+// exponential backoff if it ever stops responding.
+
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::AppState;
+
+/// `session.json` key for the user-configurable readiness timeout.
+const READY_TIMEOUT_STORE_KEY: &str = "serverReadyTimeoutSecs";
+
+/// How long `start_server_supervised` waits for the server to come up
+/// before giving up and surfacing an error, absent an override in
+/// `session.json` (see [`ready_timeout_secs`]).
+const DEFAULT_READY_TIMEOUT_SECS: u64 = 30;
+/// How often we poll while waiting for readiness.
+const READY_POLL_INTERVAL_MS: u64 = 250;
+/// How often the background watchdog checks on the running server.
+const HEALTH_CHECK_INTERVAL_SECS: u64 = 5;
+/// Consecutive failed health checks before the watchdog restarts the server.
+const FAILURE_THRESHOLD: u32 = 3;
+/// Cap on the exponential backoff between restart attempts.
+const MAX_BACKOFF_SECS: u64 = 30;
+
+/// Single source of truth for the port the bundled server listens on.
+/// `spawn_server_process` sets `PORT` to this for the Node process, and
+/// anything else that needs to talk to the backend (e.g. `uri_scheme`)
+/// should go through [`server_origin`] rather than hardcoding it again.
+pub const SERVER_PORT: u16 = 3000;
+
+/// `http://127.0.0.1:<SERVER_PORT>`, with no trailing slash.
+pub fn server_origin() -> String {
+    format!("http://127.0.0.1:{}", SERVER_PORT)
+}
+
+/// Spawn the Node.js process without waiting for it to become ready.
+/// In development the server is assumed to already be running.
+fn spawn_server_process(
+    #[allow(unused_variables)] app: &AppHandle,
+) -> Result<u32, Box<dyn std::error::Error>> {
+    println!("Starting Next.js server...");
+
+    #[cfg(debug_assertions)]
+    {
+        println!("Development mode - assuming server is already running on localhost:3000");
+        return Ok(0); // Dummy PID
+    }
+
+    #[cfg(not(debug_assertions))]
+    {
+        use std::process::{Command, Stdio};
+
+        // Get the resource directory where we'll bundle the Next.js server
+        let resource_dir = app.path().resource_dir()?;
+        let server_dir = resource_dir.join("server");
+
+        println!("Server directory: {:?}", server_dir);
+
+        if !server_dir.exists() {
+            return Err(format!("Server directory not found: {:?}", server_dir).into());
+        }
+
+        // Platform-specific paths for the bundled Node.js binary
+        let node_path = if cfg!(target_os = "macos") {
+            resource_dir
+                .parent()
+                .ok_or("Failed to get parent directory")?
+                .join("MacOS")
+                .join("node")
+        } else if cfg!(target_os = "windows") {
+            let exe_dir = std::env::current_exe()?
+                .parent()
+                .ok_or("Failed to get exe directory")?
+                .to_path_buf();
+            exe_dir.join("node.exe")
+        } else {
+            resource_dir.join("node")
+        };
+
+        println!("Using bundled Node.js at: {:?}", node_path);
+
+        if !node_path.exists() {
+            return Err(format!(
+                "Bundled Node.js not found at: {:?}\n\nPlease ensure Node.js is installed.",
+                node_path
+            )
+            .into());
+        }
+
+        let server_script = server_dir.join("server.js");
+
+        println!(
+            "Starting server with bundled Node.js: {:?} {:?}",
+            node_path, server_script
+        );
+
+        let mut command = Command::new(&node_path);
+        command
+            .arg(server_script.to_str().unwrap())
+            .current_dir(&server_dir)
+            .env("NODE_ENV", "production")
+            .env("PORT", SERVER_PORT.to_string())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        for (key, value) in crate::proxy::server_env_vars(app) {
+            command.env(key, value);
+        }
+
+        if let Ok(data_dir) = crate::data_dir::get_data_dir(app) {
+            command.env("DATA_DIR", data_dir);
+        }
+
+        let mut child = command.spawn()?;
+
+        let pid = child.id();
+        println!("Server started with PID: {}", pid);
+
+        if let Some(stdout) = child.stdout.take() {
+            crate::diagnostics::spawn_log_reader(app.clone(), stdout, "stdout");
+        }
+        if let Some(stderr) = child.stderr.take() {
+            crate::diagnostics::spawn_log_reader(app.clone(), stderr, "stderr");
+        }
+
+        Ok(pid)
+    }
+}
+
+/// How long `restart_server` waits for the old process to exit on its own
+/// before force-killing it.
+const GRACEFUL_EXIT_TIMEOUT_SECS: u64 = 10;
+/// How long to wait after a force-kill before giving up entirely.
+const FORCE_KILL_TIMEOUT_SECS: u64 = 5;
+
+/// Whether a process with the given PID is still alive.
+fn is_process_alive(pid: u32) -> bool {
+    if pid == 0 {
+        return false;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::process::Command;
+        let filter = format!("PID eq {}", pid);
+        match Command::new("tasklist").args(["/FI", &filter]).output() {
+            Ok(output) => String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()),
+            Err(_) => false,
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::process::Command;
+        Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+}
+
+/// Poll `is_process_alive` until it reports the process gone, or `timeout`
+/// elapses.
+async fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if !is_process_alive(pid) {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    !is_process_alive(pid)
+}
+
+/// Force-kill a process that didn't respond to a normal `stop_server`.
+fn force_kill(pid: u32) {
+    if pid == 0 {
+        return;
+    }
+
+    println!("Force-killing unresponsive server process (PID: {})...", pid);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::process::Command;
+        let _ = Command::new("taskkill")
+            .args(&["/PID", &pid.to_string(), "/F", "/T"])
+            .output();
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::process::Command;
+        let _ = Command::new("kill").args(&["-9", &pid.to_string()]).output();
+    }
+}
+
+/// Stop the current server, wait for it to actually exit (force-killing it
+/// if it doesn't), then relaunch it. Used when a settings change requires
+/// the backend to reload.
+#[tauri::command]
+pub async fn restart_server(app: AppHandle) -> Result<u32, String> {
+    stop_and_wait(&app).await?;
+
+    let new_pid = start_server_supervised(&app)?;
+
+    if let Some(state) = app.try_state::<AppState>() {
+        *state.server_process.lock().unwrap() = Some(new_pid);
+    }
+
+    Ok(new_pid)
+}
+
+/// Stop the currently-running server (if any) and confirm it has actually
+/// exited, force-killing it if it doesn't within `GRACEFUL_EXIT_TIMEOUT_SECS`.
+/// Used anywhere a caller needs to be sure the server is down before
+/// touching files it might still hold open.
+pub async fn stop_and_wait(app: &AppHandle) -> Result<(), String> {
+    let old_pid = app
+        .try_state::<AppState>()
+        .and_then(|state| *state.server_process.lock().unwrap());
+
+    let Some(pid) = old_pid else {
+        return Ok(());
+    };
+
+    stop_server(pid);
+
+    if !wait_for_exit(pid, Duration::from_secs(GRACEFUL_EXIT_TIMEOUT_SECS)).await {
+        force_kill(pid);
+        if !wait_for_exit(pid, Duration::from_secs(FORCE_KILL_TIMEOUT_SECS)).await {
+            return Err(format!("Server process {} would not terminate", pid));
+        }
+    }
+
+    // The PID we just stopped is no longer valid - clear it immediately so
+    // a caller whose subsequent `start_server_supervised` fails doesn't
+    // leave state pointing at a dead process (which `uri_scheme` and
+    // `diagnostics::get_server_health` would otherwise read as "running").
+    if let Some(state) = app.try_state::<AppState>() {
+        *state.server_process.lock().unwrap() = None;
+    }
+
+    Ok(())
+}
+
+/// Stop the server process identified by `pid`.
+pub fn stop_server(pid: u32) {
+    if pid == 0 {
+        return; // Development mode or invalid PID
+    }
+
+    println!("Stopping server process (PID: {})...", pid);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::process::Command;
+        let _ = Command::new("taskkill")
+            .args(&["/PID", &pid.to_string(), "/F"])
+            .output();
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::process::Command;
+        let _ = Command::new("kill").arg(pid.to_string()).output();
+    }
+
+    println!("Server stopped");
+}
+
+/// Check whether the server is reachable, either via an HTTP response or,
+/// failing that, a bare TCP connect.
+async fn check_reachable() -> bool {
+    if let Ok(resp) = reqwest::Client::new()
+        .get(format!("{}/", server_origin()))
+        .timeout(Duration::from_secs(2))
+        .send()
+        .await
+    {
+        let status = resp.status();
+        if status.is_success() || status.is_redirection() {
+            return true;
+        }
+    }
+
+    tokio::net::TcpStream::connect(("127.0.0.1", SERVER_PORT))
+        .await
+        .is_ok()
+}
+
+/// The readiness timeout to use, in seconds: whatever's persisted in
+/// `session.json` under `READY_TIMEOUT_STORE_KEY`, or `DEFAULT_READY_TIMEOUT_SECS`
+/// if nothing has been set.
+fn ready_timeout_secs(app: &AppHandle) -> u64 {
+    app.store("session.json")
+        .ok()
+        .and_then(|store| store.get(READY_TIMEOUT_STORE_KEY))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_READY_TIMEOUT_SECS)
+}
+
+#[tauri::command]
+pub fn server_ready_timeout_get(app: AppHandle) -> Result<u64, String> {
+    Ok(ready_timeout_secs(&app))
+}
+
+#[tauri::command]
+pub fn server_ready_timeout_set(app: AppHandle, secs: u64) -> Result<bool, String> {
+    let store = app.store("session.json").map_err(|e| e.to_string())?;
+    store.set(READY_TIMEOUT_STORE_KEY, serde_json::json!(secs));
+    store.save().map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// Poll `check_reachable` until it succeeds or `timeout` elapses.
+async fn wait_until_ready(timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if check_reachable().await {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(READY_POLL_INTERVAL_MS)).await;
+    }
+    false
+}
+
+/// Spawn the server and block until it is actually answering requests (or
+/// the readiness timeout elapses), emitting `server://status` events along
+/// the way. Replaces the old "spawn and sleep(2s)" approach.
+pub fn start_server_supervised(app: &AppHandle) -> Result<u32, String> {
+    let _ = app.emit("server://status", "starting");
+
+    let pid = spawn_server_process(app).map_err(|e| e.to_string())?;
+
+    let timeout_secs = ready_timeout_secs(app);
+    // Callers include async commands and the watchdog task, which already run
+    // on the async runtime; `block_in_place` lets `block_on` wait there
+    // without panicking (and is a plain call from `setup`).
+    let ready = tokio::task::block_in_place(|| {
+        tauri::async_runtime::block_on(wait_until_ready(Duration::from_secs(timeout_secs)))
+    });
+
+    if !ready {
+        stop_server(pid);
+        let _ = app.emit("server://status", "failed");
+        return Err(format!(
+            "Server did not become ready within {}s",
+            timeout_secs
+        ));
+    }
+
+    let _ = app.emit("server://status", "ready");
+    Ok(pid)
+}
+
+/// Spawn the background task that keeps the server alive: probes it on a
+/// fixed interval and, after `FAILURE_THRESHOLD` consecutive failures,
+/// restarts it with exponential backoff (reset on success).
+pub fn spawn_watchdog(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut consecutive_failures = 0u32;
+        let mut backoff_secs = 1u64;
+
+        loop {
+            tokio::time::sleep(Duration::from_secs(HEALTH_CHECK_INTERVAL_SECS)).await;
+
+            // Teardown in progress - the exit handler in `main.rs` is
+            // stopping the server on purpose, so don't fight it.
+            if app
+                .try_state::<AppState>()
+                .map(|state| *state.shutting_down.lock().unwrap())
+                .unwrap_or(false)
+            {
+                println!("Watchdog stopping: app is shutting down");
+                break;
+            }
+
+            if check_reachable().await {
+                consecutive_failures = 0;
+                backoff_secs = 1;
+                if let Some(state) = app.try_state::<AppState>() {
+                    *state.last_ready.lock().unwrap() = Some(Instant::now());
+                    *state.healthy.lock().unwrap() = true;
+                }
+                continue;
+            }
+
+            consecutive_failures += 1;
+            if consecutive_failures < FAILURE_THRESHOLD {
+                continue;
+            }
+            consecutive_failures = 0;
+
+            if let Some(state) = app.try_state::<AppState>() {
+                *state.healthy.lock().unwrap() = false;
+            }
+
+            println!("Server health check failed {} times in a row, restarting...", FAILURE_THRESHOLD);
+            let _ = app.emit("server://down", ());
+            let _ = app.emit("server://status", "restarting");
+
+            let old_pid = app
+                .try_state::<AppState>()
+                .and_then(|state| *state.server_process.lock().unwrap());
+            if let Some(pid) = old_pid {
+                stop_server(pid);
+            }
+
+            tokio::time::sleep(Duration::from_secs(backoff_secs)).await;
+
+            match start_server_supervised(&app) {
+                Ok(new_pid) => {
+                    if let Some(state) = app.try_state::<AppState>() {
+                        *state.server_process.lock().unwrap() = Some(new_pid);
+                        *state.restart_count.lock().unwrap() += 1;
+                    }
+                    backoff_secs = 1;
+                }
+                Err(e) => {
+                    eprintln!("Watchdog restart failed: {}", e);
+                    backoff_secs = (backoff_secs * 2).min(MAX_BACKOFF_SECS);
+                }
+            }
+        }
+    });
+}
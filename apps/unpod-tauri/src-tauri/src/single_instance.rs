@@ -0,0 +1,28 @@
+// ============================================
+// Single Instance Enforcement
+// ============================================
+//
+// Unpod spawns and owns a backend process on a fixed port, so a second
+// launch would collide with it. Instead of letting that happen, the
+// second process hands its CLI args / deep-link URL off to the already
+// running instance and exits without spawning anything.
+
+use tauri::{AppHandle, Emitter};
+
+/// Called on the primary instance when a second launch is detected.
+/// Focuses the main window and forwards any deep-link URL found in the
+/// new process's args.
+pub fn handle_second_instance(app: &AppHandle, args: Vec<String>, _cwd: String) {
+    println!("Second instance launched with args: {:?}", args);
+
+    crate::activate_app_window(app);
+
+    let deep_link = args
+        .into_iter()
+        .skip(1) // argv[0] is the executable path
+        .find(|arg| arg.starts_with("http://") || arg.starts_with("https://") || arg.starts_with("unpod://"));
+
+    if let Some(url) = deep_link {
+        let _ = app.emit("deep-link://received", url);
+    }
+}
@@ -0,0 +1,51 @@
+// ============================================
+// OS Theme Detection
+// ============================================
+//
+// Reads the real system appearance from the main window instead of the
+// old hardcoded "light", and keeps the frontend and tray icon in sync
+// whenever macOS/Windows/Linux flips between light and dark mode.
+
+use tauri::{AppHandle, Emitter, Manager, Theme};
+
+pub fn theme_str(theme: Theme) -> &'static str {
+    match theme {
+        Theme::Dark => "dark",
+        _ => "light",
+    }
+}
+
+/// Tray icon bytes appropriate for the given theme. `include_bytes!` is
+/// resolved at compile time, so this isn't a runtime fallback - the light
+/// icon is used for anything but `Theme::Dark` because that's the only
+/// variant actually bundled alongside it.
+fn tray_icon_bytes(theme: Theme) -> &'static [u8] {
+    match theme {
+        Theme::Dark => include_bytes!("../icons/32x32-dark.png"),
+        _ => include_bytes!("../icons/32x32.png"),
+    }
+}
+
+/// Push the tray icon matching `theme`, if the tray has already been created.
+pub fn sync_tray_icon(app: &AppHandle, theme: Theme) {
+    let Some(tray) = app.tray_by_id("main-tray") else {
+        return;
+    };
+
+    match tauri::image::Image::from_bytes(tray_icon_bytes(theme)) {
+        Ok(icon) => {
+            let _ = tray.set_icon(Some(icon));
+        }
+        Err(e) => eprintln!("Failed to load tray icon for theme change: {}", e),
+    }
+}
+
+/// Forward an OS appearance change to the frontend and the tray icon.
+pub fn handle_theme_changed(app: &AppHandle, theme: Theme) {
+    println!("OS theme changed to: {}", theme_str(theme));
+    let _ = app.emit(
+        "theme://changed",
+        serde_json::json!({ "theme": theme_str(theme) }),
+    );
+    sync_tray_icon(app, theme);
+}
@@ -0,0 +1,152 @@
+// ============================================
+// Auto-Updater Commands
+// ============================================
+//
+// Gives the frontend enough information to show a real progress bar and
+// release notes instead of a silent check, and adds a consent-dialog flow
+// (`updater_install_with_dialog`) that shows the release notes before
+// installing.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Serialize, Clone)]
+pub struct UpdateCheckResult {
+    pub available: bool,
+    pub version: Option<String>,
+    pub current_version: String,
+    pub notes: Option<String>,
+    pub pub_date: Option<String>,
+}
+
+#[tauri::command]
+pub async fn updater_check_for_updates(_app: AppHandle) -> Result<UpdateCheckResult, String> {
+    #[cfg(not(debug_assertions))]
+    {
+        use tauri_plugin_updater::UpdaterExt;
+
+        let current_version = _app.package_info().version.to_string();
+        let updater = _app.updater_builder().build().map_err(|e| e.to_string())?;
+
+        match updater.check().await {
+            Ok(Some(update)) => Ok(UpdateCheckResult {
+                available: true,
+                version: Some(update.version.clone()),
+                current_version,
+                notes: update.body.clone(),
+                pub_date: update.date.map(|d| d.to_string()),
+            }),
+            Ok(None) => Ok(UpdateCheckResult {
+                available: false,
+                version: None,
+                current_version,
+                notes: None,
+                pub_date: None,
+            }),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    {
+        Err("Auto-updates disabled in development".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn updater_download_and_install(_app: AppHandle) -> Result<(), String> {
+    #[cfg(not(debug_assertions))]
+    {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::Arc;
+        use tauri_plugin_updater::UpdaterExt;
+
+        let updater = _app.updater_builder().build().map_err(|e| e.to_string())?;
+
+        if let Some(update) = updater.check().await.map_err(|e| e.to_string())? {
+            let downloaded = Arc::new(AtomicU64::new(0));
+            let app_for_progress = _app.clone();
+            let downloaded_for_progress = downloaded.clone();
+
+            update
+                .download_and_install(
+                    move |chunk_length, content_length| {
+                        let total = downloaded_for_progress.fetch_add(chunk_length as u64, Ordering::SeqCst)
+                            + chunk_length as u64;
+                        let percent = content_length.map(|len| (total as f64 / len as f64) * 100.0);
+
+                        let _ = app_for_progress.emit(
+                            "updater://download-progress",
+                            serde_json::json!({
+                                "chunk_length": chunk_length,
+                                "downloaded": total,
+                                "content_length": content_length,
+                                "percent": percent,
+                            }),
+                        );
+                    },
+                    {
+                        let app_for_finished = _app.clone();
+                        move || {
+                            let _ = app_for_finished.emit("updater://download-finished", ());
+                        }
+                    },
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(debug_assertions)]
+    {
+        Err("Auto-updates disabled in development".to_string())
+    }
+}
+
+/// Check for an update and, if one exists, show a native confirm dialog
+/// with the release notes before downloading and installing it. Returns
+/// `false` if no update is available or the user declines; after a
+/// successful install the app relaunches, so the call does not return.
+#[tauri::command]
+pub async fn updater_install_with_dialog(app: AppHandle) -> Result<bool, String> {
+    #[cfg(not(debug_assertions))]
+    {
+        use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+
+        let check = updater_check_for_updates(app.clone()).await?;
+        if !check.available {
+            return Ok(false);
+        }
+
+        let message = format!(
+            "Unpod {} is available (you're on {}).\n\n{}",
+            check.version.clone().unwrap_or_default(),
+            check.current_version,
+            check
+                .notes
+                .clone()
+                .unwrap_or_else(|| "No release notes provided.".to_string())
+        );
+
+        let confirmed = app
+            .dialog()
+            .message(message)
+            .title("Update Available")
+            .buttons(MessageDialogButtons::OkCancel)
+            .blocking_show();
+
+        if !confirmed {
+            return Ok(false);
+        }
+
+        updater_download_and_install(app.clone()).await?;
+        app.restart();
+    }
+
+    #[cfg(debug_assertions)]
+    {
+        Err("Auto-updates disabled in development".to_string())
+    }
+}
@@ -0,0 +1,110 @@
+// ============================================
+// Custom URI Scheme
+// ============================================
+//
+// Registers `unpod://` as a stable origin for the webview instead of the
+// frontend talking to `localhost:3000` directly - the backend's actual
+// port lives in one place (`server::server_origin`) so the two can't
+// drift. Requests are forwarded to the running server, which also lets us
+// inject auth headers the frontend shouldn't see and relay range-request
+// headers for streamed media. The response body is buffered in memory
+// before being handed back, since `UriSchemeResponder::respond` takes a
+// complete `Response<Vec<u8>>` rather than a chunked writer.
+
+use tauri::http::{Request, Response};
+use tauri::{Manager, UriSchemeContext, UriSchemeResponder, Wry};
+use tauri_plugin_store::StoreExt;
+
+fn error_response(status: u16, message: &str) -> Response<Vec<u8>> {
+    let body = format!(
+        "<html><body><h1>Unpod server unavailable</h1><p>{}</p></body></html>",
+        message
+    )
+    .into_bytes();
+
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "text/html")
+        .body(body)
+        .unwrap_or_else(|_| Response::new(Vec::new()))
+}
+
+fn auth_header(app: &tauri::AppHandle) -> Option<String> {
+    let store = app.store("session.json").ok()?;
+    let token = store.get("authToken")?;
+    token.as_str().map(|s| format!("Bearer {}", s))
+}
+
+/// Handler passed to `Builder::register_asynchronous_uri_scheme_protocol`.
+/// Forwards the request's method/headers/body to the backend and relays
+/// its buffered response back, returning an error page if the server isn't up.
+pub fn handle_request(
+    ctx: UriSchemeContext<'_, Wry>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app = ctx.app_handle().clone();
+
+    tauri::async_runtime::spawn(async move {
+        let server_up = app
+            .try_state::<crate::AppState>()
+            .map(|state| state.server_process.lock().unwrap().is_some())
+            .unwrap_or(false);
+
+        if !server_up {
+            responder.respond(error_response(502, "The backend isn't running right now."));
+            return;
+        }
+
+        let path_and_query = request
+            .uri()
+            .path_and_query()
+            .map(|pq| pq.as_str())
+            .unwrap_or("/");
+        let target_url = format!("{}{}", crate::server::server_origin(), path_and_query);
+
+        let method = reqwest::Method::from_bytes(request.method().as_str().as_bytes())
+            .unwrap_or(reqwest::Method::GET);
+
+        let client = reqwest::Client::new();
+        let mut builder = client.request(method, &target_url);
+
+        for (name, value) in request.headers() {
+            // The inbound `Host` reflects the `unpod://` scheme's authority,
+            // not the backend's - forwarding it as-is can trip Next.js's
+            // host/origin validation. `reqwest` sets the right `Host` for
+            // `target_url` itself, so just drop the original.
+            if name == reqwest::header::HOST {
+                continue;
+            }
+            if let Ok(value_str) = value.to_str() {
+                builder = builder.header(name.as_str(), value_str);
+            }
+        }
+
+        if let Some(auth) = auth_header(&app) {
+            builder = builder.header("Authorization", auth);
+        }
+
+        builder = builder.body(request.body().clone());
+
+        match builder.send().await {
+            Ok(upstream) => {
+                let status = upstream.status().as_u16();
+                let mut response_builder = Response::builder().status(status);
+                for (name, value) in upstream.headers() {
+                    response_builder = response_builder.header(name.as_str(), value.as_bytes());
+                }
+
+                let body = upstream.bytes().await.map(|b| b.to_vec()).unwrap_or_default();
+                match response_builder.body(body) {
+                    Ok(response) => responder.respond(response),
+                    Err(_) => responder.respond(error_response(502, "Failed to relay server response.")),
+                }
+            }
+            Err(e) => {
+                responder.respond(error_response(502, &format!("Could not reach the backend: {}", e)));
+            }
+        }
+    });
+}